@@ -9,5 +9,5 @@ fn main() {
     assert_eq!(SERIALIZED_DATA.len(), bytes_consumed);
     let parsed_fields = json_object.fields();
     assert_eq!(1, parsed_fields.len());
-    assert_eq!(JsonField::new("some_string_key", JsonValue::String("some_string_value")), parsed_fields[0]);
+    assert_eq!(JsonField::new("some_string_key", JsonValue::string("some_string_value")), parsed_fields[0]);
 }
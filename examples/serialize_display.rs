@@ -3,7 +3,7 @@ use lil_json::{ArrayJsonObject, JsonField, JsonValue};
 fn main() {
 
     let number_field = JsonField::new("some_number", JsonValue::Number(12345));
-    let string_field = JsonField::new("some_string", JsonValue::String("hello world!"));
+    let string_field = JsonField::new("some_string", JsonValue::string("hello world!"));
     let boolean_field = JsonField::new("some_boolean", JsonValue::Boolean(true));
 
     let mut json_object = ArrayJsonObject::<3>::new();
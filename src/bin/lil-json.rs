@@ -0,0 +1,252 @@
+//! validates, minifies, or pretty prints a stream of whitespace separated JSON documents from stdin to stdout
+
+use std::{io::{stdin, stdout}, process::exit};
+
+use embedded_io::Write;
+use embedded_io_adapters::std::FromStd;
+use lil_json::{serialize_json_array_pretty, serialize_json_object_pretty, AllocEscapeBuffer, JsonArray, JsonField, JsonObject, JsonParseFailure, JsonValue, ParseOptions};
+
+const READ_BUFFER_SIZE: usize = 16384; // 16 KiB
+const USAGE: &str = "usage: lil-json <validate|minify|pretty> < input.json";
+
+#[derive(Debug,Clone,Copy,PartialEq,Eq)]
+enum Command {
+    Validate,
+    Minify,
+    Pretty,
+}
+
+#[derive(Debug)]
+enum CliError {
+    Io(std::io::Error),
+    Parse(usize,JsonParseFailure),
+}
+
+impl From<std::io::Error> for CliError {
+    fn from(e: std::io::Error) -> Self {
+        CliError::Io(e)
+    }
+}
+
+/// tracks how far the buffered input has been scanned for the end of the document at its start, so each byte is scanned once & the document is only parsed when it may be complete
+#[derive(Debug,Default)]
+struct DocumentScanner {
+    offset: usize,
+    depth: usize,
+    in_string: bool,
+    escaped: bool,
+}
+
+impl DocumentScanner {
+    /// scan the bytes of `data` that haven't been scanned yet - returns true once the document that starts at `data[0]` may be complete. brackets are only counted, so the parser still has the final say.
+    fn scan(&mut self, data: &[u8]) -> bool {
+        while let Some(&byte) = data.get(self.offset) {
+            self.offset += 1;
+            if self.in_string {
+                match byte {
+                    _ if self.escaped => self.escaped = false,
+                    b'\\' => self.escaped = true,
+                    b'"' => {
+                        self.in_string = false;
+                        if self.depth == 0 {
+                            return true;
+                        }
+                    },
+                    _ => {},
+                }
+                continue;
+            }
+            match byte {
+                b'"' => self.in_string = true,
+                b'{' | b'[' => self.depth += 1,
+                b'}' | b']' => {
+                    self.depth = self.depth.saturating_sub(1);
+                    if self.depth == 0 {
+                        return true;
+                    }
+                },
+                // a number or literal at the top level ends at whitespace
+                _ if self.depth == 0 && byte.is_ascii_whitespace() => return true,
+                _ => {},
+            }
+        }
+        false
+    }
+}
+
+enum Document<'a> {
+    Object(JsonObject<Vec<JsonField<'a,'a>>>),
+    Array(JsonArray<Vec<JsonValue<'a>>>),
+    Value(JsonValue<'a>),
+}
+
+fn main() {
+    let command = match std::env::args().nth(1).as_deref() {
+        Some("validate") => Command::Validate,
+        Some("minify") => Command::Minify,
+        Some("pretty") => Command::Pretty,
+        _ => {
+            eprintln!("{}", USAGE);
+            exit(2);
+        },
+    };
+    match run(command, stdin().lock(), FromStd::new(stdout().lock())) {
+        Ok(_) => {},
+        Err(CliError::Io(e)) => {
+            eprintln!("io error: {}", e);
+            exit(1);
+        },
+        Err(CliError::Parse(offset, e)) => {
+            eprintln!("invalid JSON at byte {}: {:?}", offset, e);
+            exit(1);
+        },
+    }
+}
+
+/// parse each document from the input as soon as it is complete & write it to the output - returns the number of documents on success. a document is only parsed once the scanner finds where it may end, so input that arrives in many small reads isn't parsed again after every read.
+fn run<Input: std::io::Read, Output: Write<Error = std::io::Error>>(command: Command, mut input: Input, mut output: Output) -> Result<usize,CliError> {
+    let mut read_buffer = [0_u8; READ_BUFFER_SIZE];
+    let mut data = Vec::new();
+    let mut data_offset = 0;
+    let mut end_of_input = false;
+    let mut num_documents = 0;
+    let mut scanner = DocumentScanner::default();
+    loop {
+        let start = data.iter().position(|b: &u8| !b.is_ascii_whitespace()).unwrap_or(data.len());
+        data.drain(..start);
+        data_offset += start;
+        if data.is_empty() {
+            if end_of_input {
+                output.flush()?;
+                return Ok(num_documents);
+            }
+        } else if scanner.scan(&data) || end_of_input {
+            let escape_buffer = AllocEscapeBuffer::new();
+            match parse_document(&data, &escape_buffer, end_of_input) {
+                Ok((bytes_consumed, document)) => {
+                    write_document(command, &document, &mut output)?;
+                    data.drain(..bytes_consumed);
+                    data_offset += bytes_consumed;
+                    num_documents += 1;
+                    scanner = DocumentScanner::default();
+                    continue;
+                },
+                // the scanner continues from where it stopped
+                Err(JsonParseFailure::Incomplete) if !end_of_input => {},
+                Err(e) => return Err(CliError::Parse(data_offset, e)),
+            }
+        }
+        match input.read(&mut read_buffer)? {
+            0 => end_of_input = true,
+            n => data.extend_from_slice(read_buffer.split_at(n).0),
+        }
+    }
+}
+
+/// parse a single document, which must not start with whitespace
+fn parse_document<'a>(data: &'a [u8], escape_buffer: &'a AllocEscapeBuffer, end_of_input: bool) -> Result<(usize,Document<'a>),JsonParseFailure> {
+    match data[0] {
+        b'{' => {
+            let mut object = JsonObject::wrap(Vec::new());
+            let bytes_consumed = object.parse_alloc(data, escape_buffer)?;
+            Ok((bytes_consumed, Document::Object(object)))
+        },
+        b'[' => {
+            let mut array = JsonArray::wrap(Vec::new());
+            let bytes_consumed = array.parse_alloc(data, escape_buffer)?;
+            Ok((bytes_consumed, Document::Array(array)))
+        },
+        _ => {
+            let options = ParseOptions { complete_input: end_of_input, ..Default::default() };
+            let (bytes_consumed, value) = JsonValue::parse_alloc_escape_with_options(data, escape_buffer, &options)?;
+            Ok((bytes_consumed, Document::Value(value)))
+        },
+    }
+}
+
+fn write_document<Output: Write>(command: Command, document: &Document<'_>, output: &mut Output) -> Result<(),Output::Error> {
+    match (command, document) {
+        (Command::Validate, _) => return Ok(()),
+        (Command::Minify, Document::Object(object)) => { object.serialize(&mut *output)?; },
+        (Command::Minify, Document::Array(array)) => { array.serialize(&mut *output)?; },
+        (_, Document::Value(value)) => { value.serialize(&mut *output)?; },
+        (Command::Pretty, Document::Object(object)) => { serialize_json_object_pretty(output, object.fields(), 0).map_err(|(_written,e)| e)?; },
+        (Command::Pretty, Document::Array(array)) => { serialize_json_array_pretty(output, array.values(), 0).map_err(|(_written,e)| e)?; },
+    }
+    output.write_all(b"\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use embedded_io_adapters::std::FromStd;
+    use lil_json::{ExpectedToken, JsonParseFailure};
+    use crate::{run, CliError, Command, DocumentScanner};
+
+    const INPUT: &[u8] = b" {\"a\":1, \"b\" : \"\\u0041\"}\n[true, null] [] \"s\" 12";
+
+    fn run_to_string(command: Command, input: &[u8]) -> Result<(usize,String),CliError> {
+        let mut output = Vec::new();
+        let num_documents = run(command, input, FromStd::new(&mut output))?;
+        Ok((num_documents, String::from_utf8(output).unwrap()))
+    }
+
+    #[test]
+    fn test_minify_and_pretty() {
+        let (num_documents, minified) = run_to_string(Command::Minify, INPUT).unwrap();
+        assert_eq!(5, num_documents);
+        assert_eq!("{\"a\":1,\"b\":\"A\"}\n[true,null]\n[]\n\"s\"\n12\n", minified);
+        let (_, pretty) = run_to_string(Command::Pretty, INPUT).unwrap();
+        assert_eq!("{\n  \"a\": 1,\n  \"b\": \"A\"\n}\n[\n  true,\n  null\n]\n[]\n\"s\"\n12\n", pretty);
+        assert_eq!(Ok((5, String::new())), run_to_string(Command::Validate, INPUT).map_err(|_| ()));
+    }
+
+    /// yields its data one byte per read, like a slow pipe
+    struct OneByteReader<'a>(&'a [u8]);
+
+    impl std::io::Read for OneByteReader<'_> {
+        fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+            match self.0.split_first() {
+                Some((&byte, rest)) if !buf.is_empty() => {
+                    buf[0] = byte;
+                    self.0 = rest;
+                    Ok(1)
+                },
+                _ => Ok(0),
+            }
+        }
+    }
+
+    #[test]
+    fn test_one_byte_reads() {
+        let mut output = Vec::new();
+        assert_eq!(5, run(Command::Minify, OneByteReader(INPUT), FromStd::new(&mut output)).unwrap());
+        assert_eq!("{\"a\":1,\"b\":\"A\"}\n[true,null]\n[]\n\"s\"\n12\n", String::from_utf8(output).unwrap());
+    }
+
+    #[test]
+    fn test_document_scanner() {
+        const DOCUMENT: &[u8] = br#"{"a":["}",{"b":"\""}]}"#;
+        let mut scanner = DocumentScanner::default();
+        // the document may only be complete at its last byte, however the data is split
+        for end in 1..DOCUMENT.len() {
+            assert!(!scanner.scan(DOCUMENT.split_at(end).0), "{}", end);
+        }
+        assert!(scanner.scan(DOCUMENT));
+        assert_eq!(DOCUMENT.len(), scanner.offset);
+        let mut scanner = DocumentScanner::default();
+        assert!(!scanner.scan(b"123"));
+        assert!(scanner.scan(b"123 "));
+    }
+
+    #[test]
+    fn test_invalid_input() {
+        match run_to_string(Command::Validate, b"{} {\"a\":}") {
+            Err(CliError::Parse(3, JsonParseFailure::UnexpectedByte { expected: ExpectedToken::Value, found: b'}', offset: 5 })) => {},
+            other => panic!("unexpected result: {:?}", other),
+        }
+        match run_to_string(Command::Validate, b"[1,") {
+            Err(CliError::Parse(0, JsonParseFailure::Incomplete)) => {},
+            other => panic!("unexpected result: {:?}", other),
+        }
+    }
+}